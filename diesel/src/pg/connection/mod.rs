@@ -0,0 +1,41 @@
+//! The Postgres connection machinery.
+//!
+//! This snapshot only reproduces the pipelined batch execution slice of `PgConnection` --
+//! transaction management, the prepared statement cache, and the `Connection` trait impl all
+//! live on the real type and aren't duplicated here.
+pub(crate) mod raw;
+pub(crate) mod stmt;
+
+use raw::RawConnection;
+use stmt::pipeline::PipelinedQuery;
+use stmt::Statement;
+
+use super::result::PgResult;
+use crate::result::QueryResult;
+
+pub(crate) struct PgConnection {
+    raw_connection: RawConnection,
+}
+
+impl PgConnection {
+    /// Executes several prepared statements back-to-back using libpq pipeline mode instead of
+    /// one network round-trip per statement -- e.g. a multi-row `insert_into` split into
+    /// chunks, or several independent queries issued together.
+    ///
+    /// Returns each statement's own [`PgResult`] (or its own error) in submission order; see
+    /// [`stmt::pipeline`] for exactly how results are drained and how a mid-batch failure is
+    /// attributed to the statement that actually produced it.
+    pub(crate) fn execute_pipelined(
+        &mut self,
+        statements: &[(&Statement, &[Option<Vec<u8>>])],
+    ) -> QueryResult<Vec<QueryResult<PgResult>>> {
+        let queries = statements
+            .iter()
+            .map(|(statement, param_data)| PipelinedQuery {
+                statement,
+                param_data,
+            })
+            .collect::<Vec<_>>();
+        stmt::pipeline::execute_pipelined(&mut self.raw_connection, &queries)
+    }
+}
@@ -0,0 +1,139 @@
+//! A thin wrapper around the raw `pq_sys::PGconn` handle.
+//!
+//! Everything in [`stmt`](super::stmt) that needs to call into libpq goes through a method
+//! here rather than touching `pq_sys` directly, so the `unsafe` FFI surface stays in one place.
+#![allow(unsafe_code)] // ffi code
+extern crate pq_sys;
+
+use std::ffi::CStr;
+use std::os::raw as libc;
+use std::ptr::NonNull;
+
+use super::result::PgResult;
+use crate::result::{DatabaseErrorKind, Error, QueryResult};
+
+pub(crate) struct RawConnection {
+    internal_connection: NonNull<pq_sys::PGconn>,
+}
+
+impl RawConnection {
+    pub(super) unsafe fn send_query_params(
+        &self,
+        query: *const libc::c_char,
+        param_count: libc::c_int,
+        param_types: *const pq_sys::Oid,
+        param_values: *const *const libc::c_char,
+        param_lengths: *const libc::c_int,
+        param_formats: *const libc::c_int,
+        result_format: libc::c_int,
+    ) -> QueryResult<()> {
+        let success = pq_sys::PQsendQueryParams(
+            self.internal_connection.as_ptr(),
+            query,
+            param_count,
+            param_types,
+            param_values,
+            param_lengths,
+            param_formats,
+            result_format,
+        );
+        self.check_success(success)
+    }
+
+    pub(super) unsafe fn send_query_prepared(
+        &self,
+        name: *const libc::c_char,
+        param_count: libc::c_int,
+        param_values: *const *const libc::c_char,
+        param_lengths: *const libc::c_int,
+        param_formats: *const libc::c_int,
+        result_format: libc::c_int,
+    ) -> QueryResult<()> {
+        let success = pq_sys::PQsendQueryPrepared(
+            self.internal_connection.as_ptr(),
+            name,
+            param_count,
+            param_values,
+            param_lengths,
+            param_formats,
+            result_format,
+        );
+        self.check_success(success)
+    }
+
+    pub(super) unsafe fn prepare(
+        &self,
+        name: *const libc::c_char,
+        sql: *const libc::c_char,
+        param_count: libc::c_int,
+        param_types: *const pq_sys::Oid,
+    ) -> QueryResult<*mut pq_sys::PGresult> {
+        let success =
+            pq_sys::PQsendPrepare(self.internal_connection.as_ptr(), name, sql, param_count, param_types);
+        self.check_success(success)?;
+        Ok(pq_sys::PQgetResult(self.internal_connection.as_ptr()))
+    }
+
+    pub(super) fn enable_row_by_row_mode(&self) -> QueryResult<()> {
+        let success = unsafe { pq_sys::PQsetSingleRowMode(self.internal_connection.as_ptr()) };
+        self.check_success(success)
+    }
+
+    /// Reads the next result off the wire, or `None` once every result of the current command
+    /// (or, in pipeline mode, the current command *or* the `NULL` libpq inserts between one
+    /// command's results and the next) has been read.
+    pub(super) fn get_next_result(&self) -> QueryResult<Option<PgResult>> {
+        let raw_result = unsafe { pq_sys::PQgetResult(self.internal_connection.as_ptr()) };
+        match NonNull::new(raw_result) {
+            Some(raw_result) => PgResult::new(raw_result.as_ptr(), self).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Enters libpq pipeline mode (`PQenterPipelineMode`), letting several statements be sent
+    /// before any of their results are read back. Only valid on an idle connection with no
+    /// result pending; every call must be matched by a later [`exit_pipeline_mode`].
+    ///
+    /// [`exit_pipeline_mode`]: RawConnection::exit_pipeline_mode
+    pub(super) unsafe fn enter_pipeline_mode(&mut self) -> QueryResult<()> {
+        let success = pq_sys::PQenterPipelineMode(self.internal_connection.as_ptr());
+        self.check_success(success)
+    }
+
+    /// Queues a sync point (`PQpipelineSync`). libpq answers it with a `PGRES_PIPELINE_SYNC`
+    /// result once every statement sent before it has produced its own result(s), which is what
+    /// lets a caller tell when a pipelined batch is done.
+    pub(super) unsafe fn pipeline_sync(&mut self) -> QueryResult<()> {
+        let success = pq_sys::PQpipelineSync(self.internal_connection.as_ptr());
+        self.check_success(success)
+    }
+
+    /// Leaves pipeline mode (`PQexitPipelineMode`). libpq refuses this until every result queued
+    /// since [`enter_pipeline_mode`] -- including the trailing `PGRES_PIPELINE_SYNC` marker and
+    /// the `NULL`s between each statement's results -- has been read via [`get_next_result`].
+    ///
+    /// [`enter_pipeline_mode`]: RawConnection::enter_pipeline_mode
+    /// [`get_next_result`]: RawConnection::get_next_result
+    pub(super) unsafe fn exit_pipeline_mode(&mut self) -> QueryResult<()> {
+        let success = pq_sys::PQexitPipelineMode(self.internal_connection.as_ptr());
+        self.check_success(success)
+    }
+
+    fn check_success(&self, success: libc::c_int) -> QueryResult<()> {
+        if success == 1 {
+            Ok(())
+        } else {
+            Err(self.last_error())
+        }
+    }
+
+    fn last_error(&self) -> Error {
+        let message = unsafe {
+            CStr::from_ptr(pq_sys::PQerrorMessage(self.internal_connection.as_ptr()))
+        };
+        Error::DatabaseError(
+            DatabaseErrorKind::Unknown,
+            Box::new(message.to_string_lossy().into_owned()),
+        )
+    }
+}
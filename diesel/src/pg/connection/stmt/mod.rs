@@ -9,15 +9,58 @@ use super::result::PgResult;
 use super::statement_cache::PrepareForCache;
 use crate::pg::PgTypeMetadata;
 use crate::result::QueryResult;
-use crate::IntoSql;
 
 use super::raw::RawConnection;
 
+pub(crate) mod pipeline;
+
 pub(crate) struct Statement {
-    name: CString,
+    inner: StatementInner,
     param_formats: Vec<libc::c_int>,
 }
 
+/// Whether a statement went through `PQprepare` and has a server-side name, or is a one-shot
+/// query that skips the separate prepare round-trip entirely.
+enum StatementInner {
+    /// A statement prepared (and possibly cached) under `name` via `PQprepare`.
+    Named(CString),
+    /// A statement that is only ever executed once. `sql` and `param_types` are sent inline
+    /// with `send_query_params` instead of being prepared ahead of time, which saves a
+    /// round-trip for queries that aren't reused.
+    Unnamed {
+        sql: CString,
+        param_types: Vec<pq_sys::Oid>,
+    },
+}
+
+/// The raw pieces libpq needs to describe a statement's bind parameters: a pointer per
+/// parameter (or null for SQL `NULL`), the matching byte lengths, and the parameter count cast
+/// to `c_int`. Shared between the regular `execute` path and the pipeline mode in
+/// [`pipeline`], which both need to hand the same triple to libpq (`execute` through
+/// `send_query_params` or `send_query_prepared`, the pipeline through `send_query_prepared`).
+pub(super) fn raw_param_parts(
+    param_data: &[Option<Vec<u8>>],
+) -> QueryResult<(Vec<*const libc::c_char>, Vec<libc::c_int>, libc::c_int)> {
+    let params_pointer = param_data
+        .iter()
+        .map(|data| {
+            data.as_ref()
+                .map(|d| d.as_ptr() as *const libc::c_char)
+                .unwrap_or(ptr::null())
+        })
+        .collect::<Vec<_>>();
+    let param_lengths = param_data
+        .iter()
+        .map(|data| data.as_ref().map(|d| d.len().try_into()).unwrap_or(Ok(0)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::result::Error::SerializationError(Box::new(e)))?;
+    let param_count: libc::c_int = params_pointer
+        .len()
+        .try_into()
+        .map_err(|e| crate::result::Error::SerializationError(Box::new(e)))?;
+    Ok((params_pointer, param_lengths, param_count))
+}
+
 impl Statement {
     pub(super) fn execute(
         &self,
@@ -25,47 +68,33 @@ impl Statement {
         param_data: &[Option<Vec<u8>>],
         row_by_row: bool,
     ) -> QueryResult<PgResult> {
-        let params_pointer = param_data
-            .iter()
-            .map(|data| {
-                data.as_ref()
-                    .map(|d| d.as_ptr() as *const libc::c_char)
-                    .unwrap_or(ptr::null())
-            })
-            .collect::<Vec<_>>();
-        let param_lengths = param_data
-            .iter()
-            .map(|data| data.as_ref().map(|d| d.len().try_into()).unwrap_or(Ok(0)))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| crate::result::Error::SerializationError(Box::new(e)))?;
-        let param_count: libc::c_int = params_pointer
-            .len()
-            .try_into()
-            .map_err(|e| crate::result::Error::SerializationError(Box::new(e)))?;
-        
+        let (params_pointer, param_lengths, param_count) = raw_param_parts(param_data)?;
+
         unsafe {
-            // Always use send_query_params for unnamed statements
-            if self.name.to_bytes().is_empty() {
-                raw_connection.send_query_params(
-                    self.into_sql().as_ptr(),
+            match &self.inner {
+                // One-shot statements were never prepared, so send the SQL and parameter
+                // types inline; this is the only call libpq needs for them.
+                StatementInner::Unnamed { sql, param_types } => raw_connection.send_query_params(
+                    sql.as_ptr(),
+                    param_count,
+                    param_types_to_ptr(Some(param_types)),
+                    params_pointer.as_ptr(),
+                    param_lengths.as_ptr(),
+                    self.param_formats.as_ptr(),
+                    1,
+                ),
+                // Statements that went through `PQprepare` are executed by name.
+                StatementInner::Named(name) => raw_connection.send_query_prepared(
+                    name.as_ptr(),
                     param_count,
                     params_pointer.as_ptr(),
                     param_lengths.as_ptr(),
                     self.param_formats.as_ptr(),
                     1,
-                )
+                ),
             }
-            // For named statements, use send_query_prepared
-            raw_connection.send_query_prepared(
-                self.name.as_ptr(),
-                param_count,
-                params_pointer.as_ptr(),
-                param_lengths.as_ptr(),
-                self.param_formats.as_ptr(),
-                1,
-            );
         }?;
-        
+
         if row_by_row {
             raw_connection.enable_row_by_row_mode()?;
         }
@@ -78,37 +107,61 @@ impl Statement {
         is_cached: PrepareForCache,
         param_types: &[PgTypeMetadata],
     ) -> QueryResult<Self> {
-        let query_name = match is_cached {
-            PrepareForCache::Yes { counter } => Some(format!("__diesel_stmt_{counter}")),
-            PrepareForCache::No => None,
-        };
-        let name = query_name.as_deref();
-        let name = CString::new(name.unwrap_or(""))?;
-        let sql = CString::new(sql)?;
         let param_types_vec = param_types
             .iter()
             .map(|x| x.oid())
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| crate::result::Error::SerializationError(Box::new(e)))?;
+        let param_formats = vec![1; param_types.len()];
 
-        let internal_result = unsafe {
-            let param_count: libc::c_int = param_types
-                .len()
-                .try_into()
-                .map_err(|e| crate::result::Error::SerializationError(Box::new(e)))?;
-            raw_connection.prepare(
-                name.as_ptr(),
-                sql.as_ptr(),
-                param_count,
-                param_types_to_ptr(Some(&param_types_vec)),
-            )
-        };
-        PgResult::new(internal_result?, raw_connection)?;
-
-        Ok(Statement {
-            name,
-            param_formats: vec![1; param_types.len()],
-        })
+        match is_cached {
+            PrepareForCache::Yes { counter } => {
+                let name = CString::new(format!("__diesel_stmt_{counter}"))?;
+                let sql = CString::new(sql)?;
+
+                let internal_result = unsafe {
+                    let param_count: libc::c_int = param_types
+                        .len()
+                        .try_into()
+                        .map_err(|e| crate::result::Error::SerializationError(Box::new(e)))?;
+                    raw_connection.prepare(
+                        name.as_ptr(),
+                        sql.as_ptr(),
+                        param_count,
+                        param_types_to_ptr(Some(&param_types_vec)),
+                    )
+                };
+                PgResult::new(internal_result?, raw_connection)?;
+
+                Ok(Statement {
+                    inner: StatementInner::Named(name),
+                    param_formats,
+                })
+            }
+            // A statement that's only executed once doesn't benefit from a name or a
+            // separate `PQprepare` round-trip: `execute` sends `sql` and its bind
+            // parameters together via `send_query_params`.
+            PrepareForCache::No => Ok(Statement {
+                inner: StatementInner::Unnamed {
+                    sql: CString::new(sql)?,
+                    param_types: param_types_vec,
+                },
+                param_formats,
+            }),
+        }
+    }
+
+    /// The server-side name this statement was prepared under, or `None` if it is an unnamed,
+    /// one-shot statement (see [`StatementInner::Unnamed`]).
+    pub(super) fn name(&self) -> Option<&CString> {
+        match &self.inner {
+            StatementInner::Named(name) => Some(name),
+            StatementInner::Unnamed { .. } => None,
+        }
+    }
+
+    pub(super) fn param_formats(&self) -> &[libc::c_int] {
+        &self.param_formats
     }
 }
 
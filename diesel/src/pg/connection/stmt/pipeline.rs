@@ -0,0 +1,142 @@
+//! Pipelined execution of a batch of prepared statements.
+//!
+//! `Statement::execute` sends one query and immediately blocks on its result, which costs a
+//! full network round-trip per statement. libpq's pipeline API lets several statements be sent
+//! back-to-back and their results drained afterwards, which is a throughput win whenever
+//! round-trip latency (rather than the database itself) is the bottleneck — e.g. a multi-row
+//! `insert_into` split into chunks, or several independent queries issued together.
+//!
+//! This is an opt-in mode: [`execute_pipelined`] is only used when a caller explicitly batches
+//! statements, e.g. through
+//! [`PgConnection::execute_pipelined`](super::super::PgConnection::execute_pipelined). It has no
+//! row-by-row mode; row-by-row results stay with `Statement::execute`, a separate, unpipelined
+//! path.
+//!
+//! `enter_pipeline_mode`/`pipeline_sync`/`exit_pipeline_mode` are real wrappers around
+//! `PQenterPipelineMode`/`PQpipelineSync`/`PQexitPipelineMode` on [`RawConnection`]; see
+//! `raw.rs` for the libpq calls themselves.
+use std::ffi::CString;
+
+use super::raw_param_parts;
+use super::Statement;
+use super::{super::raw::RawConnection, super::result::PgResult};
+use crate::result::{Error, QueryResult};
+
+/// One statement and its bind parameters, queued for pipelined execution.
+pub(crate) struct PipelinedQuery<'a> {
+    pub(crate) statement: &'a Statement,
+    pub(crate) param_data: &'a [Option<Vec<u8>>],
+}
+
+/// Executes `queries` using libpq's pipeline mode, returning one [`PgResult`] per query in the
+/// same order they were submitted.
+///
+/// If a statement's own query fails, only its slot is `Err`; statements before and after it
+/// (the pipeline keeps processing, just rejecting work until the next sync) keep their own
+/// `Ok`/`Err` result, so a caller can tell exactly which statement in the batch failed. A
+/// protocol-level failure -- the pipeline desyncing, or ending before every statement produced
+/// a result -- is unrecoverable for the whole batch and is returned as the outer `Err` instead.
+pub(crate) fn execute_pipelined(
+    raw_connection: &mut RawConnection,
+    queries: &[PipelinedQuery<'_>],
+) -> QueryResult<Vec<QueryResult<PgResult>>> {
+    // Validate every statement up front: once `enter_pipeline_mode` succeeds the connection
+    // must be taken back out of pipeline mode no matter what, so an unnamed statement needs to
+    // be rejected before anything is sent rather than aborting the batch partway through.
+    let names = queries
+        .iter()
+        .map(|query| {
+            query.statement.name().ok_or_else(|| {
+                Error::QueryBuilderError(
+                    "pipelined execution requires prepared (named) statements".into(),
+                )
+            })
+        })
+        .collect::<QueryResult<Vec<_>>>()?;
+
+    unsafe {
+        raw_connection.enter_pipeline_mode()?;
+    }
+
+    let outcome = send_and_drain(raw_connection, queries, &names);
+
+    // Regardless of whether sending or draining succeeded, the connection must come back out
+    // of pipeline mode -- otherwise every later call against it (including unrelated,
+    // unpipelined `Statement::execute` calls) misbehaves for the rest of its lifetime.
+    let exit_result = unsafe { raw_connection.exit_pipeline_mode() };
+
+    let results = outcome?;
+    exit_result?;
+    Ok(results)
+}
+
+fn send_and_drain(
+    raw_connection: &mut RawConnection,
+    queries: &[PipelinedQuery<'_>],
+    names: &[&CString],
+) -> QueryResult<Vec<QueryResult<PgResult>>> {
+    for (query, name) in queries.iter().zip(names) {
+        let (params_pointer, param_lengths, param_count) = raw_param_parts(query.param_data)?;
+        unsafe {
+            raw_connection.send_query_prepared(
+                name.as_ptr(),
+                param_count,
+                params_pointer.as_ptr(),
+                param_lengths.as_ptr(),
+                query.statement.param_formats().as_ptr(),
+                1,
+            )?;
+        }
+    }
+
+    unsafe {
+        raw_connection.pipeline_sync()?;
+    }
+
+    // `PQgetResult` interleaves each command's result with a `NULL` marking the end of that
+    // command's results, so draining N commands takes 2N calls, not N.
+    let mut results = Vec::with_capacity(queries.len());
+    for _ in queries {
+        results.push(expect_one_result(raw_connection, "a pipelined statement")?);
+    }
+
+    // `PQpipelineSync` queues its own `PGRES_PIPELINE_SYNC` result (again followed by a `NULL`),
+    // which `PQexitPipelineMode` requires to have been drained before it will succeed. A failure
+    // reading it isn't attributable to any one statement, so it's surfaced as the outer `Err`.
+    if let Err(e) = expect_one_result(raw_connection, "the PGRES_PIPELINE_SYNC marker")? {
+        return Err(e);
+    }
+
+    Ok(results)
+}
+
+/// Reads exactly one result (and the `NULL` libpq emits right after it) off `raw_connection`.
+///
+/// The outer `QueryResult` is `Err` for a protocol-level desync -- the pipeline ending early, or
+/// producing more than one result where only one was expected -- which aborts the whole batch.
+/// The inner `QueryResult` is `Err` when the result itself is an error response to `what`, which
+/// is reported positionally rather than aborting anything.
+fn expect_one_result(
+    raw_connection: &mut RawConnection,
+    what: &str,
+) -> QueryResult<QueryResult<PgResult>> {
+    let result: QueryResult<PgResult> = match raw_connection.get_next_result() {
+        Ok(Some(result)) => Ok(result),
+        Ok(None) => {
+            return Err(Error::QueryBuilderError(format!(
+                "pipeline ended before producing a result for {what}"
+            )))
+        }
+        Err(e) => Err(e),
+    };
+    match raw_connection.get_next_result() {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return Err(Error::QueryBuilderError(format!(
+                "expected a single result for {what}, got more than one"
+            )))
+        }
+        Err(e) => return Err(e),
+    }
+    Ok(result)
+}
@@ -0,0 +1,162 @@
+//! Configurable `PRAGMA`s applied right after a SQLite connection is opened.
+//!
+//! These used to be statements users had to issue by hand after calling `establish`. Having
+//! them as a first-class, typed option avoids the most common `SQLITE_BUSY` and "foreign key
+//! violation went unnoticed" footguns.
+use super::SqliteConnection;
+use crate::connection::SimpleConnection;
+use crate::result::{ConnectionError, ConnectionResult, QueryResult};
+
+/// The journal mode SQLite should use for a connection.
+///
+/// See [the SQLite documentation](https://www.sqlite.org/pragma.html#pragma_journal_mode) for
+/// the meaning of each mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JournalMode {
+    /// `PRAGMA journal_mode = DELETE;` (the SQLite default)
+    Delete,
+    /// `PRAGMA journal_mode = TRUNCATE;`
+    Truncate,
+    /// `PRAGMA journal_mode = PERSIST;`
+    Persist,
+    /// `PRAGMA journal_mode = MEMORY;`
+    Memory,
+    /// `PRAGMA journal_mode = WAL;`
+    Wal,
+    /// `PRAGMA journal_mode = OFF;`
+    Off,
+}
+
+impl JournalMode {
+    fn as_sql(self) -> &'static str {
+        match self {
+            JournalMode::Delete => "DELETE",
+            JournalMode::Truncate => "TRUNCATE",
+            JournalMode::Persist => "PERSIST",
+            JournalMode::Memory => "MEMORY",
+            JournalMode::Wal => "WAL",
+            JournalMode::Off => "OFF",
+        }
+    }
+}
+
+/// Options applied to a [`SqliteConnection`](super::SqliteConnection) immediately after it is
+/// opened, and before any user query runs.
+///
+/// Construct one with [`ConnectionOptions::new`] and pass it to
+/// [`SqliteConnection::establish_with_options`](super::SqliteConnection::establish_with_options),
+/// or reuse the same options for every connection in an r2d2 pool via
+/// [`SqliteConnectionCustomizer`], which applies them to each new connection.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConnectionOptions {
+    enable_foreign_keys: bool,
+    busy_timeout: Option<std::time::Duration>,
+    journal_mode: Option<JournalMode>,
+}
+
+impl ConnectionOptions {
+    /// Creates an empty set of options. Nothing is applied until the individual `with_*`
+    /// methods are called.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Issues `PRAGMA foreign_keys = ON;` after establishing the connection when
+    /// `enable_foreign_keys` is `true`; with `false`, no `foreign_keys` pragma is issued.
+    pub fn with_foreign_keys(mut self, enable_foreign_keys: bool) -> Self {
+        self.enable_foreign_keys = enable_foreign_keys;
+        self
+    }
+
+    /// Issues `PRAGMA busy_timeout = <ms>;` after establishing the connection, so that a
+    /// connection retries on `SQLITE_BUSY` for up to `timeout` instead of failing immediately.
+    pub fn with_busy_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.busy_timeout = Some(timeout);
+        self
+    }
+
+    /// Issues `PRAGMA journal_mode = <mode>;` after establishing the connection.
+    pub fn with_journal_mode(mut self, journal_mode: JournalMode) -> Self {
+        self.journal_mode = Some(journal_mode);
+        self
+    }
+
+    /// Issues the configured `PRAGMA`s against an already-open connection.
+    ///
+    /// This is what [`SqliteConnection::establish_with_options`] calls right after
+    /// `establish`, and what [`SqliteConnectionCustomizer`] calls for every connection an
+    /// r2d2 pool creates. It is `pub(super)`, so code outside this module goes through one of
+    /// those.
+    pub(super) fn apply(&self, conn: &mut SqliteConnection) -> QueryResult<()> {
+        if self.enable_foreign_keys {
+            conn.batch_execute("PRAGMA foreign_keys = ON;")?;
+        }
+        if let Some(timeout) = self.busy_timeout {
+            conn.batch_execute(&format!("PRAGMA busy_timeout = {};", timeout.as_millis()))?;
+        }
+        if let Some(journal_mode) = self.journal_mode {
+            conn.batch_execute(&format!("PRAGMA journal_mode = {};", journal_mode.as_sql()))?;
+        }
+        Ok(())
+    }
+}
+
+impl SqliteConnection {
+    /// Opens a connection to `database_url` and applies `options` to it before returning,
+    /// so the `PRAGMA`s it configures are in effect before any user query runs.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use diesel::sqlite::{SqliteConnection, ConnectionOptions, JournalMode};
+    /// # use std::time::Duration;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let options = ConnectionOptions::new()
+    ///     .with_foreign_keys(true)
+    ///     .with_busy_timeout(Duration::from_secs(5))
+    ///     .with_journal_mode(JournalMode::Wal);
+    /// let conn = SqliteConnection::establish_with_options("test.db", options)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn establish_with_options(
+        database_url: &str,
+        options: ConnectionOptions,
+    ) -> ConnectionResult<Self> {
+        let mut conn = Self::establish(database_url)?;
+        options
+            .apply(&mut conn)
+            .map_err(ConnectionError::CouldntSetupConfiguration)?;
+        Ok(conn)
+    }
+}
+
+/// An r2d2 `CustomizeConnection` that applies the same [`ConnectionOptions`] to every physical
+/// connection a pool creates, right after it's acquired.
+///
+/// ```rust,no_run
+/// # use diesel::r2d2::{ConnectionManager, Pool};
+/// # use diesel::sqlite::{SqliteConnection, ConnectionOptions, SqliteConnectionCustomizer};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let manager = ConnectionManager::<SqliteConnection>::new("test.db");
+/// let pool = Pool::builder()
+///     .connection_customizer(Box::new(SqliteConnectionCustomizer(
+///         ConnectionOptions::new().with_foreign_keys(true),
+///     )))
+///     .build(manager)?;
+/// #     Ok(())
+/// # }
+/// ```
+#[cfg(feature = "r2d2")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SqliteConnectionCustomizer(pub ConnectionOptions);
+
+#[cfg(feature = "r2d2")]
+impl crate::r2d2::CustomizeConnection<SqliteConnection, crate::r2d2::Error>
+    for SqliteConnectionCustomizer
+{
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), crate::r2d2::Error> {
+        self.0.apply(conn).map_err(crate::r2d2::Error::QueryError)
+    }
+}
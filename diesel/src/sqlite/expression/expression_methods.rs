@@ -0,0 +1,201 @@
+//! SQLite specific expression methods that don't fit anywhere else.
+//!
+//! This is home to the marker traits used throughout
+//! `diesel::sqlite::expression::functions` (`TextOrNullableText`, `BinaryOrNullableBinary`,
+//! `TextOrNullableTextOrBinaryOrNullableBinary`, `MaybeNullableValue`) to gate which SQL types
+//! a SQLite JSON helper accepts and infer whether its result is `Nullable`, plus the `->`/`->>`
+//! JSON path operators built on top of them.
+use crate::expression::{AsExpression, Expression};
+use crate::query_builder::{AstPass, QueryFragment, QueryId};
+use crate::result::QueryResult;
+use crate::sql_types::{BigInt, Binary, Json, Jsonb, Nullable, Text};
+use crate::sqlite::Sqlite;
+
+/// Implemented for `Text` and `Nullable<Text>`.
+pub trait TextOrNullableText {}
+
+impl TextOrNullableText for Text {}
+impl TextOrNullableText for Nullable<Text> {}
+
+/// Implemented for `Binary` and `Nullable<Binary>`.
+pub trait BinaryOrNullableBinary {}
+
+impl BinaryOrNullableBinary for Binary {}
+impl BinaryOrNullableBinary for Nullable<Binary> {}
+
+/// Implemented for `Text`, `Binary`, `Json`, `Jsonb` and their `Nullable<_>` counterparts —
+/// anything SQLite's JSON functions accept as their first argument.
+pub trait TextOrNullableTextOrBinaryOrNullableBinary {}
+
+impl TextOrNullableTextOrBinaryOrNullableBinary for Text {}
+impl TextOrNullableTextOrBinaryOrNullableBinary for Nullable<Text> {}
+impl TextOrNullableTextOrBinaryOrNullableBinary for Binary {}
+impl TextOrNullableTextOrBinaryOrNullableBinary for Nullable<Binary> {}
+impl TextOrNullableTextOrBinaryOrNullableBinary for Json {}
+impl TextOrNullableTextOrBinaryOrNullableBinary for Nullable<Json> {}
+impl TextOrNullableTextOrBinaryOrNullableBinary for Jsonb {}
+impl TextOrNullableTextOrBinaryOrNullableBinary for Nullable<Jsonb> {}
+
+/// Computes the `Nullable`-aware SQL type a SQLite JSON helper should return: a non-nullable
+/// input type (e.g. `Text`) maps to `Out` itself, while a `Nullable<_>` input type maps to
+/// `Nullable<Out>`, so passing `NULL` in always yields `NULL` back out rather than a type error.
+pub trait MaybeNullableValue<ST> {
+    /// The SQL type produced for this combination of input type and desired `ST`.
+    type Out;
+}
+
+macro_rules! impl_maybe_nullable_value {
+    (@one $input:ty => ($($out:ty),+ $(,)?)) => {
+        $(
+            impl MaybeNullableValue<$out> for $input {
+                type Out = $out;
+            }
+
+            impl MaybeNullableValue<$out> for Nullable<$input> {
+                type Out = Nullable<$out>;
+            }
+        )+
+    };
+    ($($input:ty),+ $(,)? => $outs:tt) => {
+        $(
+            impl_maybe_nullable_value!(@one $input => $outs);
+        )+
+    };
+}
+
+impl_maybe_nullable_value!(Text, Binary, Json, Jsonb => (Json, Jsonb, Text, BigInt));
+
+/// The `->` and `->>` JSON path extraction operators, mirroring SQLite's `json_extract`.
+///
+/// `->` extracts a JSON sub-value and keeps it as JSON; `->>` extracts the same sub-value and
+/// converts it to text, matching the behavior of the corresponding SQLite operators.
+#[derive(Debug, Clone, Copy, QueryId)]
+#[doc(hidden)]
+pub struct JsonPathExtract<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<L, R> Expression for JsonPathExtract<L, R>
+where
+    L: Expression,
+    L::SqlType: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Json>,
+{
+    type SqlType = <L::SqlType as MaybeNullableValue<Json>>::Out;
+}
+
+impl<L, R> QueryFragment<Sqlite> for JsonPathExtract<L, R>
+where
+    L: Expression + QueryFragment<Sqlite>,
+    L::SqlType: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Json>,
+    R: QueryFragment<Sqlite>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Sqlite>) -> QueryResult<()> {
+        out.push_sql("(");
+        self.left.walk_ast(out.reborrow())?;
+        out.push_sql(" -> ");
+        self.right.walk_ast(out.reborrow())?;
+        out.push_sql(")");
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, QueryId)]
+#[doc(hidden)]
+pub struct JsonPathExtractText<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<L, R> Expression for JsonPathExtractText<L, R>
+where
+    L: Expression,
+    L::SqlType: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Text>,
+{
+    type SqlType = <L::SqlType as MaybeNullableValue<Text>>::Out;
+}
+
+impl<L, R> QueryFragment<Sqlite> for JsonPathExtractText<L, R>
+where
+    L: Expression + QueryFragment<Sqlite>,
+    L::SqlType: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Text>,
+    R: QueryFragment<Sqlite>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Sqlite>) -> QueryResult<()> {
+        out.push_sql("(");
+        self.left.walk_ast(out.reborrow())?;
+        out.push_sql(" ->> ");
+        self.right.walk_ast(out.reborrow())?;
+        out.push_sql(")");
+        Ok(())
+    }
+}
+
+/// Provides the `->` and `->>` SQLite JSON path operators as DSL methods on any JSON, JSONB,
+/// text or binary expression (the same set `json_extract` accepts).
+///
+/// These mirror `json_extract`'s trait-gated return type: extracting from a plain `Text`
+/// expression yields `Json`/`Text`, while extracting from a `Nullable<Text>` expression yields
+/// `Nullable<Json>`/`Nullable<Text>`, so a `NULL` input or missing path propagates to `NULL`.
+pub trait SqliteJsonExpressionMethods: Expression + Sized
+where
+    Self::SqlType: TextOrNullableTextOrBinaryOrNullableBinary,
+{
+    /// Creates a SQLite `->` expression, extracting the value at `path` and keeping it as JSON.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # include!("../../doctest_setup.rs");
+    /// #
+    /// # fn main() {
+    /// #     #[cfg(feature = "serde_json")]
+    /// #     run_test().unwrap();
+    /// # }
+    /// #
+    /// # #[cfg(feature = "serde_json")]
+    /// # fn run_test() -> QueryResult<()> {
+    /// #     use diesel::dsl::json;
+    /// #     use serde_json::Value;
+    /// #     use diesel::sql_types::Text;
+    /// #     let connection = &mut establish_connection();
+    ///
+    /// let result = diesel::select(json::<Text, _>(r#"{"a": {"b": 2}}"#).json_extract("$.a"))
+    ///     .get_result::<Value>(connection)?;
+    ///
+    /// assert_eq!(Value::from(serde_json::json!({"b": 2})), result);
+    ///
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn json_extract<T>(self, path: T) -> JsonPathExtract<Self, T::Expression>
+    where
+        T: AsExpression<Text>,
+        Self::SqlType: MaybeNullableValue<Json>,
+    {
+        JsonPathExtract {
+            left: self,
+            right: path.as_expression(),
+        }
+    }
+
+    /// Creates a SQLite `->>` expression, extracting the value at `path` and converting it to
+    /// text.
+    fn retrieve_as_text<T>(self, path: T) -> JsonPathExtractText<Self, T::Expression>
+    where
+        T: AsExpression<Text>,
+        Self::SqlType: MaybeNullableValue<Text>,
+    {
+        JsonPathExtractText {
+            left: self,
+            right: path.as_expression(),
+        }
+    }
+}
+
+impl<T> SqliteJsonExpressionMethods for T
+where
+    T: Expression,
+    T::SqlType: TextOrNullableTextOrBinaryOrNullableBinary,
+{
+}
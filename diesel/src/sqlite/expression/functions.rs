@@ -210,3 +210,165 @@ define_sql_function! {
     /// ```
     fn json_pretty<E: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Text>>(e: E) -> E::Out;
 }
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Extracts and returns the value at the given path from a JSON string or JSONB blob.
+    ///
+    /// If the path does not exist in `e`, `json_extract` returns `NULL`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # include!("../../doctest_setup.rs");
+    /// #
+    /// # fn main() {
+    /// #     #[cfg(feature = "serde_json")]
+    /// #     run_test().unwrap();
+    /// # }
+    /// #
+    /// # #[cfg(feature = "serde_json")]
+    /// # fn run_test() -> QueryResult<()> {
+    /// #     use diesel::dsl::json_extract;
+    /// #     use serde_json::Value;
+    /// #     use diesel::sql_types::Text;
+    /// #     let connection = &mut establish_connection();
+    ///
+    /// let result = diesel::select(json_extract::<Text, _, _>(r#"{"a": {"b": 2}}"#, "$.a.b"))
+    ///     .get_result::<Value>(connection)?;
+    ///
+    /// assert_eq!(Value::from(2), result);
+    ///
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn json_extract<E: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Json>>(e: E, path: Text) -> E::Out;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// The jsonb_extract(X, P) function returns the binary JSONB representation of the value
+    /// found by applying path P to the JSON or JSONB value X.
+    fn jsonb_extract<E: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Jsonb>>(e: E, path: Text) -> E::Out;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Constructs a JSON array from two values.
+    ///
+    /// This mirrors SQLite's variadic `json_array` function for the common two-value case;
+    /// use `sql::<Json>` directly if you need a different arity.
+    fn json_array<E1: TextOrNullableTextOrBinaryOrNullableBinary, E2: TextOrNullableTextOrBinaryOrNullableBinary>(e1: E1, e2: E2) -> Json;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// The jsonb_array(...) function is the JSONB equivalent of `json_array`.
+    fn jsonb_array<E1: TextOrNullableTextOrBinaryOrNullableBinary, E2: TextOrNullableTextOrBinaryOrNullableBinary>(e1: E1, e2: E2) -> Jsonb;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Constructs a JSON object from a key/value pair.
+    ///
+    /// This mirrors SQLite's variadic `json_object` function for the common single-pair case;
+    /// use `sql::<Json>` directly if you need a different arity.
+    fn json_object<V: TextOrNullableTextOrBinaryOrNullableBinary>(key: Text, value: V) -> Json;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// The jsonb_object(...) function is the JSONB equivalent of `json_object`.
+    fn jsonb_object<V: TextOrNullableTextOrBinaryOrNullableBinary>(key: Text, value: V) -> Jsonb;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Returns a copy of `e` with `value` inserted at `path` if `path` does not already exist.
+    /// A value already present at `path` is left unchanged.
+    fn json_insert<E: TextOrNullableTextOrBinaryOrNullableBinary, V: TextOrNullableTextOrBinaryOrNullableBinary>(e: E, path: Text, value: V) -> Json;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// The jsonb_insert(X, P, V) function is the JSONB equivalent of `json_insert`.
+    fn jsonb_insert<E: TextOrNullableTextOrBinaryOrNullableBinary, V: TextOrNullableTextOrBinaryOrNullableBinary>(e: E, path: Text, value: V) -> Jsonb;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Returns a copy of `e` with the value at `path` replaced by `value`. Unlike
+    /// `json_insert`, `path` must already exist or `e` is returned unmodified.
+    fn json_replace<E: TextOrNullableTextOrBinaryOrNullableBinary, V: TextOrNullableTextOrBinaryOrNullableBinary>(e: E, path: Text, value: V) -> Json;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// The jsonb_replace(X, P, V) function is the JSONB equivalent of `json_replace`.
+    fn jsonb_replace<E: TextOrNullableTextOrBinaryOrNullableBinary, V: TextOrNullableTextOrBinaryOrNullableBinary>(e: E, path: Text, value: V) -> Jsonb;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Returns a copy of `e` with the value at `path` set to `value`, inserting or overwriting
+    /// it as needed.
+    fn json_set<E: TextOrNullableTextOrBinaryOrNullableBinary, V: TextOrNullableTextOrBinaryOrNullableBinary>(e: E, path: Text, value: V) -> Json;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// The jsonb_set(X, P, V) function is the JSONB equivalent of `json_set`.
+    fn jsonb_set<E: TextOrNullableTextOrBinaryOrNullableBinary, V: TextOrNullableTextOrBinaryOrNullableBinary>(e: E, path: Text, value: V) -> Jsonb;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Returns a copy of `e` with the value at `path` removed.
+    fn json_remove<E: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Json>>(e: E, path: Text) -> E::Out;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// The jsonb_remove(X, P) function is the JSONB equivalent of `json_remove`.
+    fn jsonb_remove<E: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Jsonb>>(e: E, path: Text) -> E::Out;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Applies the RFC 7396 MergePatch algorithm to merge `patch` into `e` and returns the
+    /// result.
+    fn json_patch<E: TextOrNullableTextOrBinaryOrNullableBinary, P: TextOrNullableTextOrBinaryOrNullableBinary>(e: E, patch: P) -> Json;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// The jsonb_patch(X, Y) function is the JSONB equivalent of `json_patch`.
+    fn jsonb_patch<E: TextOrNullableTextOrBinaryOrNullableBinary, P: TextOrNullableTextOrBinaryOrNullableBinary>(e: E, patch: P) -> Jsonb;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Returns the "type" of the outermost element of `e` as one of the text values `"null"`, `"true"`, `"false"`, `"integer"`, `"real"`,
+    /// `"text"`, `"array"` or `"object"`.
+    fn json_type<E: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<Text>>(e: E) -> E::Out;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Returns 1 if `e` is valid JSON, and 0 otherwise.
+    fn json_valid<E: TextOrNullableTextOrBinaryOrNullableBinary>(e: E) -> Bool;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Converts a SQL text value into a JSON-encoded string, escaping it as a JSON string
+    /// literal.
+    fn json_quote<E: TextOrNullableText + MaybeNullableValue<Json>>(e: E) -> E::Out;
+}
+
+#[cfg(feature = "sqlite")]
+define_sql_function! {
+    /// Returns the number of elements in the JSON array `e`. Returns 0 if the value is not an
+    /// array.
+    fn json_array_length<E: TextOrNullableTextOrBinaryOrNullableBinary + MaybeNullableValue<BigInt>>(e: E) -> E::Out;
+}
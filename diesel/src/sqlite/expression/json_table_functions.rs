@@ -0,0 +1,188 @@
+//! Support for SQLite's table-valued JSON functions `json_each` and `json_tree`.
+//!
+//! Unlike the scalar functions in [`functions`](super::functions), these are query sources:
+//! they can only appear in a `FROM` clause (or a `JOIN`), not as an ordinary expression. They
+//! are most useful for expanding a JSON array or object column into one row per element, e.g.
+//! `diesel::dsl::json_each(tags).inner_join(posts::table)`.
+//!
+//! Each of `json_each`/`json_tree` defines its own `key`/`value`/`type_`/... column types
+//! (`json_each_columns`/`json_tree_columns`) that are only selectable against that specific
+//! query source, so selecting `json_each_columns::value` against a `json_tree` source (or
+//! vice versa) is a type error rather than silently ambiguous SQL. Using the same
+//! table-valued function twice in one query (e.g. to join two expansions against each other)
+//! is not yet supported — SQLite requires each to have a distinct alias, which this module
+//! does not emit.
+use crate::expression::{AppearsOnTable, Expression, SelectableExpression};
+use crate::query_builder::{AstPass, QueryFragment, QueryId, SelectQuery};
+use crate::query_source::QuerySource;
+use crate::result::QueryResult;
+use crate::sql_types::Text;
+use crate::sqlite::Sqlite;
+
+/// A JSON path bound to a `json_each`/`json_tree` call, restricting the expanded rows to that
+/// path. Unlike a `&'static str`, this accepts any text expression known at query-build time —
+/// a `String` built at runtime, a bind parameter, or a text column.
+type BoundJsonPath = std::sync::Arc<dyn QueryFragment<Sqlite> + Send + Sync>;
+
+macro_rules! json_table_function {
+    ($struct_name:ident, $fn_name:ident, $sql_name:expr, $columns_mod:ident) => {
+        /// The columns produced by this table-valued function, usable both in its default
+        /// selection and for explicit selection (e.g. `.select(
+        #[doc = concat!("        ", stringify!($columns_mod), "::value")]
+        /// )`).
+        pub mod $columns_mod {
+            use super::*;
+            use crate::sql_types::{BigInt, Json, Nullable};
+
+            macro_rules! json_table_column {
+                ($name:ident, $ty:ty, $sql:expr) => {
+                    #[allow(non_camel_case_types)]
+                    #[derive(Debug, Clone, Copy, QueryId)]
+                    #[doc(hidden)]
+                    pub struct $name;
+
+                    impl Expression for $name {
+                        type SqlType = $ty;
+                    }
+
+                    // Only selectable against the one query source this column module
+                    // belongs to -- not against any `QS` whatsoever.
+                    impl<E> SelectableExpression<super::$struct_name<E>> for $name {}
+                    impl<E> AppearsOnTable<super::$struct_name<E>> for $name {}
+
+                    impl QueryFragment<Sqlite> for $name {
+                        fn walk_ast<'b>(
+                            &'b self,
+                            mut out: AstPass<'_, 'b, Sqlite>,
+                        ) -> QueryResult<()> {
+                            out.push_sql($sql);
+                            Ok(())
+                        }
+                    }
+                };
+            }
+
+            json_table_column!(key, Nullable<Text>, "key");
+            json_table_column!(value, Nullable<Json>, "value");
+            json_table_column!(type_, Text, "type");
+            json_table_column!(atom, Nullable<Json>, "atom");
+            json_table_column!(id, BigInt, "id");
+            json_table_column!(parent, Nullable<BigInt>, "parent");
+            json_table_column!(fullkey, Text, "fullkey");
+            json_table_column!(path, Text, "path");
+        }
+
+        /// See the [module level documentation](self) for details.
+        pub fn $fn_name<E>(source: E) -> $struct_name<E>
+        where
+            E: Expression,
+        {
+            $struct_name { source, path: None }
+        }
+
+        #[derive(Clone)]
+        #[doc(hidden)]
+        pub struct $struct_name<E> {
+            source: E,
+            path: Option<BoundJsonPath>,
+        }
+
+        impl<E: std::fmt::Debug> std::fmt::Debug for $struct_name<E> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($struct_name))
+                    .field("source", &self.source)
+                    .field("has_path", &self.path.is_some())
+                    .finish()
+            }
+        }
+
+        // Not `#[derive(QueryId)]`: that would key the prepared statement cache on `E` alone,
+        // but `path` (set at runtime by `at_path`, after the type is fixed) changes the SQL
+        // this emits -- `json_each(x)` versus `json_each(x, ?)`. Two values of the same
+        // `$struct_name<E>` can therefore produce different SQL, so this type can never safely
+        // claim a static query id.
+        impl<E> QueryId for $struct_name<E> {
+            type QueryId = ();
+
+            const HAS_STATIC_QUERY_ID: bool = false;
+        }
+
+        impl<E> $struct_name<E> {
+            /// Restricts the expanded rows to the given JSON path, mirroring the optional
+            /// second argument of SQLite's `json_each`/`json_tree`.
+            ///
+            /// `path` can be any text expression, not just a string literal known at compile
+            /// time -- a `String` computed at runtime, a bind parameter, or a text column.
+            pub fn at_path<P>(mut self, path: P) -> Self
+            where
+                P: crate::expression::AsExpression<Text>,
+                <P as crate::expression::AsExpression<Text>>::Expression:
+                    QueryFragment<Sqlite> + Send + Sync + 'static,
+            {
+                self.path = Some(std::sync::Arc::new(path.as_expression()));
+                self
+            }
+        }
+
+        impl<E> QuerySource for $struct_name<E>
+        where
+            E: QueryFragment<Sqlite> + Clone,
+        {
+            type FromClause = Self;
+            type DefaultSelection = (
+                $columns_mod::key,
+                $columns_mod::value,
+                $columns_mod::type_,
+                $columns_mod::atom,
+                $columns_mod::id,
+                $columns_mod::parent,
+                $columns_mod::fullkey,
+                $columns_mod::path,
+            );
+
+            fn from_clause(&self) -> Self::FromClause {
+                self.clone()
+            }
+
+            fn default_selection(&self) -> Self::DefaultSelection {
+                (
+                    $columns_mod::key,
+                    $columns_mod::value,
+                    $columns_mod::type_,
+                    $columns_mod::atom,
+                    $columns_mod::id,
+                    $columns_mod::parent,
+                    $columns_mod::fullkey,
+                    $columns_mod::path,
+                )
+            }
+        }
+
+        impl<E> QueryFragment<Sqlite> for $struct_name<E>
+        where
+            E: QueryFragment<Sqlite>,
+        {
+            fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Sqlite>) -> QueryResult<()> {
+                out.push_sql($sql_name);
+                out.push_sql("(");
+                self.source.walk_ast(out.reborrow())?;
+                if let Some(ref path) = self.path {
+                    out.push_sql(", ");
+                    path.walk_ast(out.reborrow())?;
+                }
+                out.push_sql(")");
+                Ok(())
+            }
+        }
+
+        impl<E> SelectQuery for $struct_name<E>
+        where
+            E: QueryFragment<Sqlite> + Clone,
+        {
+            type SqlType = <<Self as QuerySource>::DefaultSelection as Expression>::SqlType;
+        }
+    };
+}
+
+json_table_function!(JsonEach, json_each, "json_each", json_each_columns);
+json_table_function!(JsonTree, json_tree, "json_tree", json_tree_columns);
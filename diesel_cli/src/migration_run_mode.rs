@@ -0,0 +1,229 @@
+//! Execution modes for `diesel migration run`: the default "one connection transaction per
+//! migration" mode, and an opt-in `--all-in-one-transaction` mode that wraps the pending
+//! migrations in a shared outer transaction so a failure in migration N rolls back 1..N
+//! atomically. A `run_in_transaction = false` migration ends that transaction, so the
+//! migrations after it share a new one and a later failure does not roll back those before
+//! it. In both modes, migrations run in the order given and a `run_in_transaction =
+//! false` migration always gets a fresh connection of its own, never the shared one -- except
+//! against a SQLite in-memory database, where a second connection can't reach the first one's
+//! schema at all, so that combination is rejected rather than silently misbehaving.
+//!
+//! `diesel migration run`'s argument parsing (in `main.rs`) is responsible for reading the
+//! `--all-in-one-transaction` flag and the target backend off the `DATABASE_URL`, and calling
+//! [`run_migrations`] with them; this module only owns the decision of how to sequence and
+//! transact the migrations once that's known.
+use std::fmt;
+use std::path::Path;
+
+use diesel::connection::Connection;
+
+/// Per-migration configuration read from a migration directory's `metadata.toml`, alongside
+/// its `up.sql`/`down.sql`.
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationMetadata {
+    /// Whether this migration may run inside a transaction. Defaults to `true`. Set to
+    /// `false` in `metadata.toml` for statements that cannot run inside a transaction at all,
+    /// e.g. Postgres `CREATE INDEX CONCURRENTLY`.
+    pub run_in_transaction: bool,
+}
+
+impl Default for MigrationMetadata {
+    fn default() -> Self {
+        MigrationMetadata {
+            run_in_transaction: true,
+        }
+    }
+}
+
+impl MigrationMetadata {
+    /// Reads `metadata.toml` out of `migration_dir`, defaulting every field (in particular
+    /// `run_in_transaction = true`) when the file is absent or doesn't set a given key.
+    pub fn read_from_directory(migration_dir: &Path) -> Result<Self, MigrationRunError> {
+        let metadata_path = migration_dir.join("metadata.toml");
+        if !metadata_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&metadata_path)
+            .map_err(|e| MigrationRunError::ReadMetadata(metadata_path.clone(), e))?;
+        let table: toml::Value = contents
+            .parse()
+            .map_err(|e| MigrationRunError::ParseMetadata(metadata_path.clone(), e))?;
+        let run_in_transaction = table
+            .get("run_in_transaction")
+            .and_then(toml::Value::as_bool)
+            .unwrap_or(true);
+
+        Ok(Self { run_in_transaction })
+    }
+}
+
+/// The database backend a migration run is targeting. Needed up front because MySQL has no
+/// transactional DDL, so it cannot honor `--all-in-one-transaction` the way Postgres and
+/// SQLite can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Pg,
+    Sqlite,
+    Mysql,
+}
+
+impl Backend {
+    /// Whether this backend can roll back schema-changing statements (`CREATE TABLE`,
+    /// `ALTER TABLE`, ...) as part of a transaction. MySQL implicitly commits DDL statements,
+    /// so wrapping migrations in an outer transaction would silently fail to protect them.
+    fn supports_transactional_ddl(self) -> bool {
+        !matches!(self, Backend::Mysql)
+    }
+}
+
+#[derive(Debug)]
+pub enum MigrationRunError {
+    ReadMetadata(std::path::PathBuf, std::io::Error),
+    ParseMetadata(std::path::PathBuf, toml::de::Error),
+    /// `--all-in-one-transaction` was requested against a backend without transactional DDL.
+    AllInOneTransactionUnsupported(Backend),
+    /// A `run_in_transaction = false` migration was found, but `database_url` names a SQLite
+    /// in-memory database. Establishing a second connection to it would not reconnect to the
+    /// schema built up so far -- it would open an entirely separate, empty database -- so there
+    /// is no connection this migration could safely run on.
+    StandaloneMigrationNeedsRealConnection,
+    /// Couldn't open the dedicated connection a `run_in_transaction = false` migration runs on.
+    Establish(diesel::ConnectionError),
+    Migration(diesel::result::Error),
+}
+
+impl fmt::Display for MigrationRunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationRunError::ReadMetadata(path, e) => {
+                write!(f, "Could not read {}: {e}", path.display())
+            }
+            MigrationRunError::ParseMetadata(path, e) => {
+                write!(f, "Could not parse {}: {e}", path.display())
+            }
+            MigrationRunError::AllInOneTransactionUnsupported(backend) => write!(
+                f,
+                "--all-in-one-transaction is not supported for {backend:?}: it has no \
+                 transactional DDL, so wrapping migrations in a transaction would not actually \
+                 roll back schema changes on failure. Re-run without that flag to commit each \
+                 migration separately."
+            ),
+            MigrationRunError::StandaloneMigrationNeedsRealConnection => write!(
+                f,
+                "This migration set includes a `run_in_transaction = false` migration, but \
+                 DATABASE_URL names a SQLite in-memory database. A second connection to \
+                 \":memory:\" opens an unrelated, empty database rather than reconnecting to the \
+                 one earlier migrations ran against, so there is no connection this migration \
+                 could safely run on. Use an on-disk database file, or drop \
+                 `run_in_transaction = false` from this migration if it doesn't actually need it."
+            ),
+            MigrationRunError::Establish(e) => {
+                write!(f, "Could not open a connection for a standalone migration: {e}")
+            }
+            MigrationRunError::Migration(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for MigrationRunError {}
+
+impl From<diesel::result::Error> for MigrationRunError {
+    fn from(e: diesel::result::Error) -> Self {
+        MigrationRunError::Migration(e)
+    }
+}
+
+/// One pending migration, paired with the `run_in_transaction` flag read from its
+/// `metadata.toml`.
+pub struct PendingMigration<'a> {
+    pub name: &'a str,
+    pub metadata: MigrationMetadata,
+}
+
+/// Runs `migrations` against `conn`, strictly in the order given, calling `run_one` to actually
+/// apply each one.
+///
+/// Every migration flagged `run_in_transaction = false` -- in either mode -- runs on a fresh
+/// connection of its own, established from `database_url`, rather than on `conn`: such a
+/// migration (e.g. Postgres `CREATE INDEX CONCURRENTLY`) cannot run inside *any* transaction
+/// block, including one a prior migration might have left open on the shared connection. This
+/// fresh connection cannot see session-scoped state (temp tables, a `SET`-established session
+/// variable, ...) a prior migration left on `conn`; a standalone migration that depends on such
+/// state needs to re-establish it itself. `database_url` naming a SQLite in-memory database is
+/// rejected up front rather than silently running the migration against an unrelated empty
+/// database -- see [`MigrationRunError::StandaloneMigrationNeedsRealConnection`].
+///
+/// In the default mode (`all_in_one_transaction: false`) every other migration commits on its
+/// own against `conn`, exactly as today. When `all_in_one_transaction` is set, `conn` wraps each
+/// maximal contiguous run of transactable migrations in one shared transaction, so a failure
+/// partway through rolls back everything run so far in that run; this never reorders migrations
+/// relative to one another, so a later migration's schema assumptions about an earlier one
+/// always hold. Requesting `all_in_one_transaction` against MySQL is rejected up front, since
+/// MySQL has no transactional DDL for that transaction to roll back.
+pub fn run_migrations<Conn>(
+    conn: &mut Conn,
+    database_url: &str,
+    backend: Backend,
+    migrations: &[PendingMigration<'_>],
+    all_in_one_transaction: bool,
+    mut run_one: impl FnMut(&mut Conn, &PendingMigration<'_>) -> Result<(), diesel::result::Error>,
+) -> Result<(), MigrationRunError>
+where
+    Conn: Connection,
+{
+    if all_in_one_transaction && !backend.supports_transactional_ddl() {
+        return Err(MigrationRunError::AllInOneTransactionUnsupported(backend));
+    }
+
+    let has_standalone_migration = migrations.iter().any(|m| !m.metadata.run_in_transaction);
+    if has_standalone_migration && is_sqlite_in_memory_url(backend, database_url) {
+        return Err(MigrationRunError::StandaloneMigrationNeedsRealConnection);
+    }
+
+    let mut index = 0;
+    while index < migrations.len() {
+        if !migrations[index].metadata.run_in_transaction {
+            let mut standalone_conn =
+                Conn::establish(database_url).map_err(MigrationRunError::Establish)?;
+            run_one(&mut standalone_conn, &migrations[index])?;
+            index += 1;
+            continue;
+        }
+
+        // Collect the maximal run of transactable migrations starting here, so they run as one
+        // transaction (in `all_in_one_transaction` mode) without skipping ahead of the
+        // standalone migration that will have interrupted it.
+        let start = index;
+        while index < migrations.len() && migrations[index].metadata.run_in_transaction {
+            index += 1;
+        }
+        let batch = &migrations[start..index];
+
+        if all_in_one_transaction {
+            conn.transaction(|conn| {
+                for migration in batch {
+                    run_one(conn, migration)?;
+                }
+                Ok(())
+            })
+            .map_err(MigrationRunError::Migration)?;
+        } else {
+            for migration in batch {
+                run_one(conn, migration)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `database_url` names a SQLite database that only exists for the lifetime of one
+/// connection -- `:memory:`, the empty string, or a `file::memory:` URI -- rather than an
+/// on-disk file a second connection would reconnect to.
+fn is_sqlite_in_memory_url(backend: Backend, database_url: &str) -> bool {
+    backend == Backend::Sqlite
+        && (database_url.is_empty()
+            || database_url == ":memory:"
+            || database_url.starts_with("file::memory:"))
+}
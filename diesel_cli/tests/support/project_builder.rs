@@ -154,6 +154,17 @@ impl Project {
         fs::remove_dir_all(file).unwrap();
     }
 
+    /// Runs `diesel migration run --all-in-one-transaction`, which wraps the pending
+    /// migrations in a shared outer transaction so a failure in migration N rolls back 1..N
+    /// atomically. Migrations whose `metadata.toml` sets `run_in_transaction = false` are
+    /// excluded and run on their own connection instead; each one ends the current shared
+    /// transaction, and the migrations after it share a new one.
+    pub fn run_migrations_all_in_one_transaction(&self) -> TestCommand {
+        self.command("migration")
+            .arg("run")
+            .arg("--all-in-one-transaction")
+    }
+
     pub fn migration_dir_in_directory(&self, directory: &str) -> String {
         let migration_path = self.directory.path().join(directory);
         migration_path.display().to_string()
@@ -163,6 +174,19 @@ impl Project {
         self.create_migration_in_directory("migrations", name, up, down, config);
     }
 
+    /// Like [`Self::create_migration`], but the migration's `metadata.toml` sets
+    /// `run_in_transaction = false`, for testing statements that cannot run inside a
+    /// transaction (e.g. Postgres `CREATE INDEX CONCURRENTLY`).
+    pub fn create_migration_outside_transaction(&self, name: &str, up: &str, down: Option<&str>) {
+        self.create_migration_in_directory(
+            "migrations",
+            name,
+            up,
+            down,
+            Some("run_in_transaction = false\n"),
+        );
+    }
+
     pub fn create_migration_in_directory(
         &self,
         directory: &str,
@@ -182,6 +206,8 @@ impl Project {
             down_file.write_all(down.as_bytes()).unwrap();
         }
 
+        // Per-migration metadata, e.g. `run_in_transaction = false`. See the `metadata.toml`
+        // handling in `migrations_internals` for the full set of recognized keys.
         if let Some(config) = config {
             let mut metadata_file = fs::File::create(migration_path.join("metadata.toml")).unwrap();
             metadata_file.write_all(config.as_bytes()).unwrap();